@@ -5,13 +5,19 @@
 
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::Emitter;
+use tokio::sync::{broadcast, mpsc};
 
 use crate::logging;
 
 /// Audio device information.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct AudioDevice {
+    /// Stable identity key the frontend can persist across restarts.
+    /// Disambiguates devices that share a display name.
+    pub id: String,
     /// Device name.
     pub name: String,
     /// Whether this is the default device.
@@ -29,6 +35,21 @@ pub struct AudioStreamConfig {
     pub channels: u16,
     /// Speaker label for this stream.
     pub speaker: String,
+    /// Name of the device actually feeding this stream, `None` if the
+    /// system default was used.
+    pub device_name: Option<String>,
+    /// When set, frames for this stream are downmixed to mono and linearly
+    /// resampled to this target before being emitted as `audio-chunk`, so
+    /// `sample_rate`/`channels` above already describe the post-resample
+    /// format ASR pipelines expect.
+    pub output_format: Option<OutputFormat>,
+}
+
+/// Downmix + resample target for a capture's `audio-chunk` frames.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct OutputFormat {
+    /// Target sample rate in Hz, e.g. `16000` for speech pipelines.
+    pub sample_rate: u32,
 }
 
 /// Audio chunk payload for the frontend.
@@ -44,26 +65,647 @@ pub struct AudioChunk {
 static CAPTURING_MIC: AtomicBool = AtomicBool::new(false);
 static CAPTURING_LOOPBACK: AtomicBool = AtomicBool::new(false);
 
+/// A timestamped, decoded PCM frame produced by a capture thread. Consumers
+/// (the sidecar forwarder, the level meter, an optional file recorder)
+/// subscribe to [`frame_bus`] independently instead of the capture thread
+/// pushing to each of them directly.
+#[derive(Debug, Clone)]
+struct AudioFrame {
+    /// Speaker label ("user"/"other").
+    source: String,
+    sample_rate: u32,
+    channels: u16,
+    samples: Vec<i16>,
+    /// Wall-clock time this frame was produced, milliseconds since the
+    /// Unix epoch. Lets [`start_mixer_consumer`] tell a stalled source from
+    /// one that simply hasn't delivered its next chunk yet.
+    produced_at_ms: u128,
+}
+
+/// Milliseconds since the Unix epoch, for timestamping frames at production.
+fn now_ms() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Control messages a capture producer listens for alongside the
+/// `CAPTURING_*` atomics, so `pause_capture`/`resume_capture`/`stop_capture`
+/// can steer a running capture without tearing it down.
+#[derive(Debug, Clone)]
+enum AudioControlMessage {
+    /// Stop the producer as soon as it next polls the control channel.
+    Stop,
+    /// Gate the stream/audio client without tearing it down, so resuming
+    /// doesn't re-initialize the device or drop the first chunks.
+    Pause,
+    /// Un-gate a previously paused stream/audio client.
+    Resume,
+    /// Re-open the stream/audio client against a different device without
+    /// tearing down this producer's thread or control channel. `None` means
+    /// fall back to the system default.
+    SwitchDevice { device_name: Option<String> },
+}
+
+/// Per-consumer liveness, surfaced to the frontend as `audio-consumer-status`.
+#[derive(Debug, Serialize, Clone)]
+struct ConsumerStatus {
+    consumer: String,
+    active: bool,
+}
+
+/// Message published on the frame bus: either a produced audio frame, or a
+/// reset signal for one source. Consumers that carry per-session state keyed
+/// by source (a [`Resampler`]'s fractional position, a [`MixSourceState`]'s
+/// jitter buffer) must drop it on `Reset`, so a session that stops and
+/// restarts doesn't interpolate or mix across the boundary between the two.
+#[derive(Debug, Clone)]
+enum FrameBusEvent {
+    Frame(AudioFrame),
+    Reset { source: String },
+}
+
+static FRAME_BUS: OnceLock<broadcast::Sender<FrameBusEvent>> = OnceLock::new();
+
+/// Shared broadcast channel frames are produced onto and consumers
+/// subscribe to. Capacity bounds how far a slow consumer can lag before it
+/// starts missing frames, without blocking the producer.
+fn frame_bus() -> broadcast::Sender<FrameBusEvent> {
+    FRAME_BUS.get_or_init(|| broadcast::channel(64).0).clone()
+}
+
+/// Tell consumers to drop any per-session state they hold for `source`,
+/// e.g. because its capture just stopped or is switching devices.
+fn reset_source(source: &str) {
+    let _ = frame_bus().send(FrameBusEvent::Reset {
+        source: source.to_string(),
+    });
+}
+
+/// Control-channel senders for the currently running mic/loopback producers,
+/// if any.
+static MIC_CONTROL: Mutex<Option<mpsc::Sender<AudioControlMessage>>> = Mutex::new(None);
+static LOOPBACK_CONTROL: Mutex<Option<mpsc::Sender<AudioControlMessage>>> = Mutex::new(None);
+
+/// Downmix+resample target per source ("user"/"other"), set by
+/// `start_microphone_capture`/`start_loopback_capture` when the caller asks
+/// for one and read by [`start_sidecar_forwarder`] as frames arrive.
+static OUTPUT_FORMAT: Mutex<Option<std::collections::HashMap<String, OutputFormat>>> =
+    Mutex::new(None);
+
+fn set_output_format(source: &str, format: Option<OutputFormat>) {
+    let mut guard = OUTPUT_FORMAT.lock().unwrap();
+    let map = guard.get_or_insert_with(std::collections::HashMap::new);
+    match format {
+        Some(format) => {
+            map.insert(source.to_string(), format);
+        }
+        None => {
+            map.remove(source);
+        }
+    }
+}
+
+fn get_output_format(source: &str) -> Option<OutputFormat> {
+    OUTPUT_FORMAT
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|map| map.get(source).copied())
+}
+
+/// Downmix interleaved i16 samples to mono by averaging each frame's
+/// channels.
+fn downmix_to_mono(samples: &[i16], channels: u16) -> Vec<i16> {
+    let channels = channels.max(1) as usize;
+    if channels == 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| {
+            let sum: i32 = frame.iter().map(|&s| s as i32).sum();
+            (sum / channels as i32) as i16
+        })
+        .collect()
+}
+
+/// Linear resampler for one mono source, carrying its fractional source
+/// position and last sample across chunk boundaries so there's no click at
+/// the seam between one chunk's output and the next.
+struct Resampler {
+    /// Position into the current chunk's samples, in source-sample units;
+    /// may be negative, meaning "before this chunk's first sample" (i.e.
+    /// interpolate against `last_sample`).
+    pos: f64,
+    /// Last sample of the previous chunk, used as the left edge when `pos`
+    /// is negative.
+    last_sample: i16,
+    seeded: bool,
+}
+
+impl Resampler {
+    fn new() -> Self {
+        Self {
+            pos: 0.0,
+            last_sample: 0,
+            seeded: false,
+        }
+    }
+
+    /// Resample mono `input` at `source_rate` Hz to `target_rate` Hz.
+    fn process(&mut self, input: &[i16], source_rate: u32, target_rate: u32) -> Vec<i16> {
+        if input.is_empty() || source_rate == 0 || target_rate == 0 {
+            return Vec::new();
+        }
+        if !self.seeded {
+            self.last_sample = input[0];
+            self.seeded = true;
+        }
+
+        let step = source_rate as f64 / target_rate as f64;
+        let len = input.len() as isize;
+        let mut output = Vec::new();
+        let mut pos = self.pos;
+
+        loop {
+            let index = pos.floor() as isize;
+            if index + 1 > len - 1 {
+                break;
+            }
+            let frac = pos - pos.floor();
+            let s0 = if index < 0 {
+                self.last_sample
+            } else {
+                input[index as usize]
+            } as f64;
+            let s1 = if index + 1 < 0 {
+                self.last_sample
+            } else {
+                input[(index + 1) as usize]
+            } as f64;
+            let value = (s0 * (1.0 - frac) + s1 * frac)
+                .round()
+                .clamp(i16::MIN as f64, i16::MAX as f64);
+            output.push(value as i16);
+            pos += step;
+        }
+
+        self.pos = pos - len as f64;
+        self.last_sample = input[input.len() - 1];
+        output
+    }
+}
+
+fn report_consumer_status(app: &tauri::AppHandle, consumer: &str, active: bool) {
+    let _ = app.emit(
+        "audio-consumer-status",
+        ConsumerStatus {
+            consumer: consumer.to_string(),
+            active,
+        },
+    );
+}
+
+/// Subscribe to the frame bus and re-emit each frame as the `audio-chunk`
+/// event the frontend/sidecar already expect, decoupling that wire format
+/// from how capture threads produce frames.
+pub fn start_sidecar_forwarder(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut rx = frame_bus().subscribe();
+        report_consumer_status(&app, "sidecar_forwarder", true);
+        let mut resamplers: std::collections::HashMap<String, Resampler> =
+            std::collections::HashMap::new();
+        while let Ok(event) = rx.recv().await {
+            let frame = match event {
+                FrameBusEvent::Frame(frame) => frame,
+                FrameBusEvent::Reset { source } => {
+                    resamplers.remove(&source);
+                    continue;
+                }
+            };
+            let samples = match get_output_format(&frame.source) {
+                Some(format) => {
+                    let mono = downmix_to_mono(&frame.samples, frame.channels);
+                    let resampler = resamplers
+                        .entry(frame.source.clone())
+                        .or_insert_with(Resampler::new);
+                    resampler.process(&mono, frame.sample_rate, format.sample_rate)
+                }
+                None => frame.samples,
+            };
+
+            let bytes: Vec<u8> = samples.iter().flat_map(|&s| s.to_le_bytes()).collect();
+            let payload = AudioChunk {
+                speaker: frame.source,
+                data: bytes,
+            };
+            let _ = app.emit("audio-chunk", payload);
+        }
+        report_consumer_status(&app, "sidecar_forwarder", false);
+    });
+}
+
+/// Subscribe to the frame bus and feed a per-source [`LevelMeter`],
+/// decoupling level metering from the capture threads.
+pub fn start_level_meter_consumer(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut rx = frame_bus().subscribe();
+        report_consumer_status(&app, "level_meter", true);
+        let mut meters: std::collections::HashMap<String, LevelMeter> =
+            std::collections::HashMap::new();
+        while let Ok(event) = rx.recv().await {
+            let frame = match event {
+                FrameBusEvent::Frame(frame) => frame,
+                FrameBusEvent::Reset { source } => {
+                    meters.remove(&source);
+                    continue;
+                }
+            };
+            let meter = meters.entry(frame.source.clone()).or_insert_with(|| {
+                LevelMeter::new(app.clone(), &frame.source, frame.sample_rate, frame.channels)
+            });
+            meter.push(&frame.samples);
+        }
+        report_consumer_status(&app, "level_meter", false);
+    });
+}
+
+/// Common rate mic and loopback are resampled to before mixing.
+const MIX_SAMPLE_RATE: u32 = 16000;
+/// How often the mixer drains its jitter buffers and emits an `audio-mix`
+/// chunk.
+const MIX_TICK: Duration = Duration::from_millis(20);
+/// How long a source may go without a frame before the mixer logs it as
+/// stalled (it still fills that source's track with silence every tick
+/// regardless, so the other track never waits on it).
+const MIX_STALL_GAP: Duration = Duration::from_millis(200);
+
+/// `audio-mix` event payload: mic and loopback resampled to a common rate
+/// and interleaved into one wall-clock-aligned stereo stream (left = user,
+/// right = other), with silence filling any tick a source stalled on.
+#[derive(Debug, Serialize, Clone)]
+struct MixedAudioChunk {
+    sample_rate: u32,
+    channels: u16,
+    /// Raw interleaved PCM bytes (16-bit LE).
+    data: Vec<u8>,
+}
+
+/// Per-source mixer state: resampled samples waiting to be drained, the
+/// resampler carrying that source's fractional position across frames, and
+/// when its last frame arrived (to detect a stall).
+#[derive(Default)]
+struct MixSourceState {
+    pending: std::collections::VecDeque<i16>,
+    resampler: Option<Resampler>,
+    last_frame_at_ms: Option<u128>,
+    stalled: bool,
+}
+
+impl MixSourceState {
+    fn push_frame(&mut self, frame: &AudioFrame) {
+        self.last_frame_at_ms = Some(frame.produced_at_ms);
+        self.stalled = false;
+        let mono = downmix_to_mono(&frame.samples, frame.channels);
+        let resampler = self.resampler.get_or_insert_with(Resampler::new);
+        self.pending
+            .extend(resampler.process(&mono, frame.sample_rate, MIX_SAMPLE_RATE));
+    }
+
+    /// Take exactly `tick_samples` mono samples for this tick, padding with
+    /// silence if the source hasn't produced enough (stalled or slower than
+    /// real time). Returns `true` the moment this source is newly observed
+    /// as stalled, so the caller can log the transition rather than every
+    /// tick the stall persists.
+    fn drain_tick(&mut self, tick_samples: usize, now_ms: u128) -> (Vec<i16>, bool) {
+        let mut newly_stalled = false;
+        if self.pending.len() < tick_samples {
+            let is_stalled = self
+                .last_frame_at_ms
+                .map(|last| now_ms.saturating_sub(last) >= MIX_STALL_GAP.as_millis())
+                .unwrap_or(true);
+            if is_stalled && !self.stalled {
+                newly_stalled = true;
+            }
+            self.stalled = is_stalled;
+        }
+
+        let mut samples: Vec<i16> = self.pending.drain(..self.pending.len().min(tick_samples)).collect();
+        samples.resize(tick_samples, 0);
+        (samples, newly_stalled)
+    }
+}
+
+/// Subscribe to the frame bus and feed a shared jitter-buffered mixer state
+/// per source; a tick every [`MIX_TICK`] reconciles the two rates and
+/// emits one aligned `audio-mix` chunk, filling gaps with silence rather
+/// than waiting on a stalled source.
+pub fn start_mixer_consumer(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut rx = frame_bus().subscribe();
+        report_consumer_status(&app, "mixer", true);
+
+        let mut sources: std::collections::HashMap<String, MixSourceState> =
+            std::collections::HashMap::new();
+        sources.insert("user".to_string(), MixSourceState::default());
+        sources.insert("other".to_string(), MixSourceState::default());
+        let tick_samples = (MIX_SAMPLE_RATE as u64 * MIX_TICK.as_millis() as u64 / 1000) as usize;
+        let mut ticker = tokio::time::interval(MIX_TICK);
+
+        loop {
+            tokio::select! {
+                event = rx.recv() => {
+                    match event {
+                        Ok(FrameBusEvent::Frame(frame)) => {
+                            sources.entry(frame.source.clone()).or_default().push_frame(&frame);
+                        }
+                        Ok(FrameBusEvent::Reset { source }) => {
+                            sources.insert(source, MixSourceState::default());
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    }
+                }
+                _ = ticker.tick() => {
+                    // Unlike the frame-driven consumers above, this tick fires
+                    // on a fixed interval regardless of whether anything is
+                    // capturing; skip emitting (and draining) while neither
+                    // source is active instead of pushing silent audio-mix
+                    // events at idle.
+                    if !CAPTURING_MIC.load(Ordering::SeqCst)
+                        && !CAPTURING_LOOPBACK.load(Ordering::SeqCst)
+                    {
+                        continue;
+                    }
+
+                    let now = now_ms();
+                    let (user, user_newly_stalled) =
+                        sources.get_mut("user").unwrap().drain_tick(tick_samples, now);
+                    let (other, other_newly_stalled) =
+                        sources.get_mut("other").unwrap().drain_tick(tick_samples, now);
+
+                    if user_newly_stalled {
+                        logging::append_log("Mixer: microphone source stalled, filling silence");
+                    }
+                    if other_newly_stalled {
+                        logging::append_log("Mixer: loopback source stalled, filling silence");
+                    }
+
+                    let mut data = Vec::with_capacity(tick_samples * 4);
+                    for i in 0..tick_samples {
+                        data.extend_from_slice(&user[i].to_le_bytes());
+                        data.extend_from_slice(&other[i].to_le_bytes());
+                    }
+
+                    let _ = app.emit(
+                        "audio-mix",
+                        MixedAudioChunk {
+                            sample_rate: MIX_SAMPLE_RATE,
+                            channels: 2,
+                            data,
+                        },
+                    );
+                }
+            }
+        }
+
+        report_consumer_status(&app, "mixer", false);
+    });
+}
+
+/// How often, in milliseconds, `audio-level` events are emitted while a
+/// capture is active.
+const DEFAULT_LEVEL_INTERVAL_MS: u64 = 50;
+
+/// Voice-activity detector configuration with open/close hysteresis so
+/// short dips in level (a breath, a plosive) don't chop speech into pieces.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct VadConfig {
+    /// dBFS level at/above which speech is considered to have started.
+    pub open_db: f32,
+    /// dBFS level below which speech is considered to have stopped.
+    pub close_db: f32,
+    /// Consecutive quiet ~50ms frames to wait before closing.
+    pub hangover_frames: u32,
+}
+
+/// Level-metering and voice-activity configuration, user-adjustable via
+/// [`set_audio_level_config`].
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct AudioLevelConfig {
+    /// Minimum interval between `audio-level` events, in milliseconds.
+    pub interval_ms: u64,
+    /// Voice-activity detector thresholds and hangover.
+    pub vad: VadConfig,
+}
+
+static LEVEL_CONFIG: Mutex<AudioLevelConfig> = Mutex::new(AudioLevelConfig {
+    interval_ms: DEFAULT_LEVEL_INTERVAL_MS,
+    vad: VadConfig {
+        open_db: -35.0,
+        close_db: -45.0,
+        hangover_frames: 10,
+    },
+});
+
+/// Update the level-metering/VAD configuration used by subsequently-started
+/// captures.
+#[tauri::command]
+pub fn set_audio_level_config(config: AudioLevelConfig) {
+    if let Ok(mut guard) = LEVEL_CONFIG.lock() {
+        *guard = config;
+    }
+}
+
+/// Read the current level-metering/VAD configuration.
+#[tauri::command]
+pub fn get_audio_level_config() -> AudioLevelConfig {
+    LEVEL_CONFIG
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or(AudioLevelConfig {
+            interval_ms: DEFAULT_LEVEL_INTERVAL_MS,
+            vad: VadConfig {
+                open_db: -35.0,
+                close_db: -45.0,
+                hangover_frames: 10,
+            },
+        })
+}
+
+/// `audio-level` event payload.
+#[derive(Debug, Serialize, Clone)]
+struct AudioLevelEvent {
+    /// Speaker label this level reading belongs to ("user"/"other").
+    source: String,
+    /// Rolling RMS level in dBFS.
+    rms_db: f64,
+    /// Peak sample level in dBFS, over the same frame as `rms_db`.
+    peak_db: f64,
+}
+
+/// Convert a linear amplitude in `[0, 1]` to dBFS, floored to avoid `-inf`.
+fn amplitude_to_db(amplitude: f64) -> f64 {
+    if amplitude <= 0.0 {
+        -96.0
+    } else {
+        20.0 * amplitude.log10()
+    }
+}
+
+/// Decode interleaved PCM bytes (16-bit int or 32-bit float, whichever the
+/// loopback device's mix format uses) into i16 samples for the frame bus.
+fn decode_pcm_to_i16(bytes: &[u8], bytes_per_sample: usize) -> Vec<i16> {
+    match bytes_per_sample {
+        2 => bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect(),
+        4 => bytes
+            .chunks_exact(4)
+            .map(|b| {
+                let sample = f32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+                (sample * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Rolling RMS/peak meter plus a hysteresis-based voice-activity detector
+/// for one capture source. Fed ~50ms frames of i16 samples as they arrive.
+struct LevelMeter {
+    source: String,
+    app: tauri::AppHandle,
+    vad: VadConfig,
+    interval: Duration,
+    last_emit: Instant,
+    frame_len: usize,
+    pending: Vec<i16>,
+    active: bool,
+    hangover_remaining: u32,
+}
+
+impl LevelMeter {
+    fn new(app: tauri::AppHandle, source: &str, sample_rate: u32, channels: u16) -> Self {
+        let config = get_audio_level_config();
+        let frame_len = ((sample_rate as f32 * 0.05) as usize * channels.max(1) as usize).max(1);
+        Self {
+            source: source.to_string(),
+            app,
+            vad: config.vad,
+            interval: Duration::from_millis(config.interval_ms.max(1)),
+            last_emit: Instant::now(),
+            frame_len,
+            pending: Vec::with_capacity(frame_len),
+            active: false,
+            hangover_remaining: 0,
+        }
+    }
+
+    /// Feed freshly captured i16 samples, computing RMS/peak over ~50ms
+    /// frames and emitting `audio-level`/`voice-active`/`voice-idle` events.
+    fn push(&mut self, samples: &[i16]) {
+        self.pending.extend_from_slice(samples);
+        while self.pending.len() >= self.frame_len {
+            let frame: Vec<i16> = self.pending.drain(..self.frame_len).collect();
+            self.process_frame(&frame);
+        }
+    }
+
+    fn process_frame(&mut self, frame: &[i16]) {
+        let sum_sq: f64 = frame.iter().map(|&s| (s as f64) * (s as f64)).sum();
+        let rms = (sum_sq / frame.len().max(1) as f64).sqrt();
+        let peak = frame
+            .iter()
+            .map(|&s| s.unsigned_abs() as f64)
+            .fold(0.0_f64, f64::max);
+
+        let rms_db = amplitude_to_db(rms / i16::MAX as f64);
+        let peak_db = amplitude_to_db(peak / i16::MAX as f64);
+
+        self.update_vad(rms_db as f32);
+
+        if self.last_emit.elapsed() >= self.interval {
+            self.last_emit = Instant::now();
+            let _ = self.app.emit(
+                "audio-level",
+                AudioLevelEvent {
+                    source: self.source.clone(),
+                    rms_db,
+                    peak_db,
+                },
+            );
+        }
+    }
+
+    fn update_vad(&mut self, rms_db: f32) {
+        if self.active {
+            if rms_db < self.vad.close_db {
+                if self.hangover_remaining == 0 {
+                    self.active = false;
+                    let _ = self.app.emit("voice-idle", self.source.clone());
+                } else {
+                    self.hangover_remaining -= 1;
+                }
+            } else {
+                self.hangover_remaining = self.vad.hangover_frames;
+            }
+        } else if rms_db >= self.vad.open_db {
+            self.active = true;
+            self.hangover_remaining = self.vad.hangover_frames;
+            let _ = self.app.emit("voice-active", self.source.clone());
+        }
+    }
+}
+
 /// Start audio capture from microphone and system audio.
 ///
 /// # Arguments
 ///
-/// * `window` - Tauri window handle for emitting events.
+/// * `app` - Tauri app handle for emitting events.
 ///
 /// # Returns
 ///
 /// Result indicating success or error message.
 #[tauri::command]
-pub async fn start_capture(window: tauri::Window) -> Result<AudioStreamConfig, String> {
-    start_microphone_capture(window).await
+pub async fn start_capture(app: tauri::AppHandle) -> Result<AudioStreamConfig, String> {
+    start_microphone_capture(app, None, None).await
+}
+
+/// Find an input device by name, matching against the same enumeration
+/// [`get_audio_devices`] walks. Falls back to the system default when
+/// `device_name` is `None` or doesn't match any enumerated device.
+fn find_input_device(device_name: Option<&str>) -> Option<cpal::Device> {
+    use cpal::traits::HostTrait;
+
+    let host = cpal::default_host();
+    if let Some(name) = device_name {
+        use cpal::traits::DeviceTrait;
+        if let Ok(input_devices) = host.input_devices() {
+            if let Some(device) = input_devices.into_iter().find(|d| d.name().as_deref() == Ok(name)) {
+                return Some(device);
+            }
+        }
+    }
+    host.default_input_device()
 }
 
 /// Start microphone audio capture using CPAL.
+///
+/// `device_name` picks a specific input device by the name
+/// [`get_audio_devices`] reports for it; `None` uses the system default.
+/// `output_format`, when set, downmixes and resamples emitted chunks to
+/// that target instead of the device's native rate/channels.
 #[tauri::command]
 pub async fn start_microphone_capture(
-    window: tauri::Window,
+    app: tauri::AppHandle,
+    device_name: Option<String>,
+    output_format: Option<OutputFormat>,
 ) -> Result<AudioStreamConfig, String> {
-    use cpal::traits::{DeviceTrait, HostTrait};
+    use cpal::traits::DeviceTrait;
 
     if CAPTURING_MIC.load(Ordering::SeqCst) {
         return Err("Microphone capture already active".to_string());
@@ -72,33 +714,56 @@ pub async fn start_microphone_capture(
     logging::append_log("Starting microphone capture");
     CAPTURING_MIC.store(true, Ordering::SeqCst);
 
-    let host = cpal::default_host();
-    let device = host
-        .default_input_device()
-        .ok_or("No input device available")?;
+    let device = find_input_device(device_name.as_deref()).ok_or("No input device available")?;
     let config = device.default_input_config().map_err(|e| e.to_string())?;
     let stream_config: cpal::StreamConfig = config.clone().into();
 
-    let info = AudioStreamConfig {
-        sample_rate: stream_config.sample_rate.0,
-        channels: stream_config.channels,
-        speaker: "user".to_string(),
+    let resolved_device_name = device.name().ok();
+    if let Some(name) = &resolved_device_name {
+        let current_id = enumerate_input_devices()
+            .into_iter()
+            .find(|d| &d.name == name)
+            .map(|d| d.id);
+        *CURRENT_MIC_DEVICE_ID.lock().unwrap() = current_id;
+    }
+
+    set_output_format("user", output_format);
+
+    let info = match output_format {
+        Some(format) => AudioStreamConfig {
+            sample_rate: format.sample_rate,
+            channels: 1,
+            speaker: "user".to_string(),
+            device_name: resolved_device_name,
+            output_format,
+        },
+        None => AudioStreamConfig {
+            sample_rate: stream_config.sample_rate.0,
+            channels: stream_config.channels,
+            speaker: "user".to_string(),
+            device_name: resolved_device_name,
+            output_format: None,
+        },
     };
 
-    // Spawn audio capture thread
-    let window_clone = window.clone();
+    // Spawn audio capture thread. The control channel lets stop_capture,
+    // pause_capture, and resume_capture steer the producer without tearing
+    // it down.
+    let (control_tx, control_rx) = mpsc::channel(8);
+    *MIC_CONTROL.lock().unwrap() = Some(control_tx);
+
+    let app_clone = app.clone();
     let device_clone = device;
     let config_clone = config;
     std::thread::spawn(move || {
-        let window_for_error = window_clone.clone();
-        if let Err(e) = capture_audio_loop(window_clone, device_clone, config_clone) {
+        if let Err(e) = capture_audio_loop(device_clone, config_clone, control_rx) {
             eprintln!("Audio capture error: {}", e);
             logging::append_log(&format!("Microphone capture error: {e}"));
-            let _ = window_for_error.emit("audio-error", e.to_string());
+            let _ = app_clone.emit("audio-error", e.to_string());
         }
     });
 
-    let _ = window.emit("audio-config", info.clone());
+    let _ = app.emit("audio-config", info.clone());
 
     Ok(info)
 }
@@ -107,36 +772,60 @@ pub async fn start_microphone_capture(
 ///
 /// # Arguments
 ///
-/// * `window` - Tauri window handle for emitting events.
+/// * `app` - Tauri app handle for emitting events.
 ///
 /// # Returns
 ///
 /// Stream configuration for the captured audio.
+///
+/// `device_name` picks a specific render device to loop back by its WASAPI
+/// friendly name; `None` uses the system default. `output_format`, when
+/// set, downmixes and resamples emitted chunks to that target instead of
+/// the device's native rate/channels.
 #[tauri::command]
-pub async fn start_loopback_capture(window: tauri::Window) -> Result<AudioStreamConfig, String> {
+pub async fn start_loopback_capture(
+    app: tauri::AppHandle,
+    device_name: Option<String>,
+    output_format: Option<OutputFormat>,
+) -> Result<AudioStreamConfig, String> {
     if CAPTURING_LOOPBACK.load(Ordering::SeqCst) {
         return Err("Loopback capture already active".to_string());
     }
 
     logging::append_log("Starting loopback capture");
     CAPTURING_LOOPBACK.store(true, Ordering::SeqCst);
+    set_output_format("other", output_format);
+
+    let (control_tx, control_rx) = mpsc::channel(8);
+    *LOOPBACK_CONTROL.lock().unwrap() = Some(control_tx);
 
-    let window_clone = window.clone();
+    let app_clone = app.clone();
     std::thread::spawn(move || {
-        let window_for_error = window_clone.clone();
-        if let Err(e) = capture_loopback_audio(window_clone) {
+        if let Err(e) = capture_loopback_audio(app_clone.clone(), device_name, output_format, control_rx)
+        {
             eprintln!("Loopback capture error: {}", e);
             logging::append_log(&format!("Loopback capture error: {e}"));
-            let _ = window_for_error.emit("audio-error", e.to_string());
+            let _ = app_clone.emit("audio-error", e.to_string());
         }
     });
 
     // We cannot know the exact format synchronously; emit a default config
     // and the real config will be sent with the first chunks.
-    Ok(AudioStreamConfig {
-        sample_rate: 48000,
-        channels: 2,
-        speaker: "other".to_string(),
+    Ok(match output_format {
+        Some(format) => AudioStreamConfig {
+            sample_rate: format.sample_rate,
+            channels: 1,
+            speaker: "other".to_string(),
+            device_name: None,
+            output_format,
+        },
+        None => AudioStreamConfig {
+            sample_rate: 48000,
+            channels: 2,
+            speaker: "other".to_string(),
+            device_name: None,
+            output_format: None,
+        },
     })
 }
 
@@ -149,10 +838,85 @@ pub async fn start_loopback_capture(window: tauri::Window) -> Result<AudioStream
 pub async fn stop_capture() -> Result<(), String> {
     CAPTURING_MIC.store(false, Ordering::SeqCst);
     CAPTURING_LOOPBACK.store(false, Ordering::SeqCst);
+    if let Some(tx) = MIC_CONTROL.lock().unwrap().clone() {
+        let _ = tx.try_send(AudioControlMessage::Stop);
+    }
+    if let Some(tx) = LOOPBACK_CONTROL.lock().unwrap().clone() {
+        let _ = tx.try_send(AudioControlMessage::Stop);
+    }
+    *CURRENT_MIC_DEVICE_ID.lock().unwrap() = None;
+    *CURRENT_LOOPBACK_DEVICE_ID.lock().unwrap() = None;
+    set_output_format("user", None);
+    set_output_format("other", None);
+    // Consumers (resamplers, the mixer's jitter buffers) key per-session
+    // state by source; reset it so the next session doesn't interpolate or
+    // mix against state left over from this one.
+    reset_source("user");
+    reset_source("other");
     logging::append_log("Audio capture stopped");
     Ok(())
 }
 
+/// Pause active capture(s) without tearing down the underlying `cpal::Stream`
+/// or `wasapi::AudioClient`, so resuming is instant and doesn't re-initialize
+/// the device.
+#[tauri::command]
+pub async fn pause_capture() -> Result<(), String> {
+    if let Some(tx) = MIC_CONTROL.lock().unwrap().clone() {
+        let _ = tx.try_send(AudioControlMessage::Pause);
+    }
+    if let Some(tx) = LOOPBACK_CONTROL.lock().unwrap().clone() {
+        let _ = tx.try_send(AudioControlMessage::Pause);
+    }
+    logging::append_log("Audio capture paused");
+    Ok(())
+}
+
+/// Resume capture(s) previously paused with [`pause_capture`].
+#[tauri::command]
+pub async fn resume_capture() -> Result<(), String> {
+    if let Some(tx) = MIC_CONTROL.lock().unwrap().clone() {
+        let _ = tx.try_send(AudioControlMessage::Resume);
+    }
+    if let Some(tx) = LOOPBACK_CONTROL.lock().unwrap().clone() {
+        let _ = tx.try_send(AudioControlMessage::Resume);
+    }
+    logging::append_log("Audio capture resumed");
+    Ok(())
+}
+
+/// Switch the active microphone capture to a different input device, or to
+/// the system default if `device_name` is `None`, without tearing down the
+/// capture thread or control channel. No-op if microphone capture isn't
+/// running.
+#[tauri::command]
+pub async fn switch_microphone_device(device_name: Option<String>) -> Result<(), String> {
+    if let Some(tx) = MIC_CONTROL.lock().unwrap().clone() {
+        let _ = tx.try_send(AudioControlMessage::SwitchDevice { device_name });
+    }
+    logging::append_log("Microphone device switch requested");
+    Ok(())
+}
+
+/// Switch the active loopback capture to a different render device, or to
+/// the system default if `device_name` is `None`, without tearing down the
+/// capture thread or control channel. No-op if loopback capture isn't
+/// running.
+#[tauri::command]
+pub async fn switch_loopback_device(device_name: Option<String>) -> Result<(), String> {
+    if let Some(tx) = LOOPBACK_CONTROL.lock().unwrap().clone() {
+        let _ = tx.try_send(AudioControlMessage::SwitchDevice { device_name });
+    }
+    logging::append_log("Loopback device switch requested");
+    Ok(())
+}
+
+/// Whether microphone capture is currently active. Used by the global
+/// hotkey to decide whether to start or stop capture in toggle mode.
+pub(crate) fn is_mic_capturing() -> bool {
+    CAPTURING_MIC.load(Ordering::SeqCst)
+}
+
 /// Get list of available audio devices.
 ///
 /// # Returns
@@ -160,12 +924,21 @@ pub async fn stop_capture() -> Result<(), String> {
 /// List of audio devices or error message.
 #[tauri::command]
 pub async fn get_audio_devices() -> Result<Vec<AudioDevice>, String> {
+    let mut devices = enumerate_input_devices();
+    devices.extend(enumerate_output_devices());
+    Ok(devices)
+}
+
+/// Enumerate input devices, assigning each a stable identity key. Devices
+/// sharing a display name are disambiguated by their position among same-
+/// named devices in this enumeration pass.
+fn enumerate_input_devices() -> Vec<AudioDevice> {
     use cpal::traits::{DeviceTrait, HostTrait};
 
     let host = cpal::default_host();
     let mut devices = Vec::new();
+    let mut seen_names: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
 
-    // Get input devices (microphones)
     if let Ok(input_devices) = host.input_devices() {
         let default_input = host.default_input_device();
 
@@ -176,7 +949,12 @@ pub async fn get_audio_devices() -> Result<Vec<AudioDevice>, String> {
                     .map(|d| d.name().ok() == Some(name.clone()))
                     .unwrap_or(false);
 
+                let ordinal = seen_names.entry(name.clone()).or_insert(0);
+                let id = format!("input:{name}#{ordinal}");
+                *ordinal += 1;
+
                 devices.push(AudioDevice {
+                    id,
                     name,
                     is_default,
                     device_type: "input".to_string(),
@@ -185,18 +963,387 @@ pub async fn get_audio_devices() -> Result<Vec<AudioDevice>, String> {
         }
     }
 
-    Ok(devices)
+    devices
+}
+
+/// Enumerate output/render devices, assigning each a stable identity key. On
+/// Windows this walks the WASAPI render endpoints [`capture_loopback_audio`]
+/// loops back from and keys each by its endpoint ID; elsewhere it falls back
+/// to cpal's output devices, keyed by name and ordinal the same way
+/// [`enumerate_input_devices`] does.
+fn enumerate_output_devices() -> Vec<AudioDevice> {
+    #[cfg(target_os = "windows")]
+    {
+        use wasapi::{DeviceCollection, DeviceEnumerator, Direction};
+
+        let mut devices = Vec::new();
+        // Ordinal fallback only, for the rare device that doesn't report a
+        // persistent endpoint ID; real WASAPI endpoints always do.
+        let mut seen_names: std::collections::HashMap<String, usize> =
+            std::collections::HashMap::new();
+
+        let default_name = DeviceEnumerator::new()
+            .and_then(|enumerator| enumerator.get_default_device(&Direction::Render))
+            .and_then(|device| device.get_friendlyname())
+            .ok();
+
+        if let Ok(collection) = DeviceCollection::new(&Direction::Render) {
+            if let Ok(count) = collection.get_nbr_devices() {
+                for idx in 0..count {
+                    let Ok(device) = collection.get_device_at_index(idx) else {
+                        continue;
+                    };
+                    let Ok(name) = device.get_friendlyname() else {
+                        continue;
+                    };
+
+                    let is_default = default_name.as_deref() == Some(name.as_str());
+                    // The WASAPI endpoint ID is stable across enumeration
+                    // order and app restarts, unlike `name#ordinal`, which
+                    // shifts if devices are enumerated in a different order.
+                    let id = match device.get_id() {
+                        Ok(endpoint_id) => format!("output:{endpoint_id}"),
+                        Err(_) => {
+                            let ordinal = seen_names.entry(name.clone()).or_insert(0);
+                            let fallback = format!("output:{name}#{ordinal}");
+                            *ordinal += 1;
+                            fallback
+                        }
+                    };
+
+                    devices.push(AudioDevice {
+                        id,
+                        name,
+                        is_default,
+                        device_type: "output".to_string(),
+                    });
+                }
+            }
+        }
+
+        devices
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use cpal::traits::{DeviceTrait, HostTrait};
+
+        let host = cpal::default_host();
+        let mut devices = Vec::new();
+        let mut seen_names: std::collections::HashMap<String, usize> =
+            std::collections::HashMap::new();
+
+        if let Ok(output_devices) = host.output_devices() {
+            let default_output = host.default_output_device();
+
+            for device in output_devices {
+                if let Ok(name) = device.name() {
+                    let is_default = default_output
+                        .as_ref()
+                        .map(|d| d.name().ok() == Some(name.clone()))
+                        .unwrap_or(false);
+
+                    let ordinal = seen_names.entry(name.clone()).or_insert(0);
+                    let id = format!("output:{name}#{ordinal}");
+                    *ordinal += 1;
+
+                    devices.push(AudioDevice {
+                        id,
+                        name,
+                        is_default,
+                        device_type: "output".to_string(),
+                    });
+                }
+            }
+        }
+
+        devices
+    }
+}
+
+/// `audio-devices-changed` event payload.
+#[derive(Debug, Serialize, Clone)]
+struct DeviceDelta {
+    added: Vec<AudioDevice>,
+    removed: Vec<AudioDevice>,
+}
+
+/// Identity key of the device currently feeding microphone capture, if any.
+static CURRENT_MIC_DEVICE_ID: Mutex<Option<String>> = Mutex::new(None);
+
+/// Identity key of the render device currently feeding loopback capture, if
+/// any.
+static CURRENT_LOOPBACK_DEVICE_ID: Mutex<Option<String>> = Mutex::new(None);
+
+/// How often the device watcher re-enumerates devices.
+const DEVICE_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Re-enumerate both input and output devices, returning `(inputs, outputs)`.
+fn enumerate_all_devices() -> (Vec<AudioDevice>, Vec<AudioDevice>) {
+    (enumerate_input_devices(), enumerate_output_devices())
+}
+
+/// Start a background thread that periodically re-enumerates input and
+/// output/loopback devices, emitting `audio-devices-changed` with the
+/// added/removed deltas and `capture-device-lost` if the device feeding an
+/// active capture vanishes.
+pub fn start_device_watcher(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let (mut known_inputs, mut known_outputs) = enumerate_all_devices();
+        loop {
+            std::thread::sleep(DEVICE_WATCH_INTERVAL);
+
+            let (current_inputs, current_outputs) = enumerate_all_devices();
+            let added: Vec<AudioDevice> = current_inputs
+                .iter()
+                .chain(current_outputs.iter())
+                .filter(|d| {
+                    !known_inputs
+                        .iter()
+                        .chain(known_outputs.iter())
+                        .any(|k| k.id == d.id)
+                })
+                .cloned()
+                .collect();
+            let removed: Vec<AudioDevice> = known_inputs
+                .iter()
+                .chain(known_outputs.iter())
+                .filter(|k| {
+                    !current_inputs
+                        .iter()
+                        .chain(current_outputs.iter())
+                        .any(|d| d.id == k.id)
+                })
+                .cloned()
+                .collect();
+
+            if !added.is_empty() || !removed.is_empty() {
+                let _ = app.emit(
+                    "audio-devices-changed",
+                    DeviceDelta {
+                        added: added.clone(),
+                        removed: removed.clone(),
+                    },
+                );
+
+                if CAPTURING_MIC.load(Ordering::SeqCst) {
+                    if let Some(current_id) = CURRENT_MIC_DEVICE_ID.lock().unwrap().clone() {
+                        if removed.iter().any(|d| d.id == current_id) {
+                            let _ = app.emit("capture-device-lost", "user");
+                            logging::append_log(
+                                "Microphone device lost, falling back to system default",
+                            );
+                            let app_for_fallback = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let _ = stop_capture().await;
+                                let _ = start_microphone_capture(app_for_fallback, None, None).await;
+                            });
+                        }
+                    }
+                }
+
+                if CAPTURING_LOOPBACK.load(Ordering::SeqCst) {
+                    if let Some(current_id) = CURRENT_LOOPBACK_DEVICE_ID.lock().unwrap().clone() {
+                        if removed.iter().any(|d| d.id == current_id) {
+                            let _ = app.emit("capture-device-lost", "other");
+                            logging::append_log(
+                                "Loopback device lost, falling back to system default",
+                            );
+                            let app_for_fallback = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let _ = stop_capture().await;
+                                let _ = start_loopback_capture(app_for_fallback, None, None).await;
+                            });
+                        }
+                    }
+                }
+            }
+
+            known_inputs = current_inputs;
+            known_outputs = current_outputs;
+        }
+    });
 }
 
 /// Main audio capture loop.
 ///
-/// Captures audio from the default input device and sends it to the frontend.
+/// Captures audio from the given input device and pushes frames onto the
+/// [`frame_bus`] for consumers to pick up; it no longer emits or meters
+/// anything itself.
 fn capture_audio_loop(
-    window: tauri::Window,
     device: cpal::Device,
     config: cpal::SupportedStreamConfig,
+    mut control_rx: mpsc::Receiver<AudioControlMessage>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     use cpal::traits::{DeviceTrait, StreamTrait};
+
+    let mut device = device;
+    let mut config = config;
+
+    // Outer loop rebuilds the stream in place when `SwitchDevice` asks for a
+    // different device, without tearing down this thread or control
+    // channel. Inner loop pauses/resumes the current stream in place.
+    loop {
+        let stream = build_stream_for_device(&device, &config)?;
+        stream.play()?;
+
+        let mut switch_to = None;
+        while CAPTURING_MIC.load(Ordering::SeqCst) {
+            match control_rx.try_recv() {
+                Ok(AudioControlMessage::Stop) => return Ok(()),
+                Ok(AudioControlMessage::Pause) => {
+                    let _ = stream.pause();
+                }
+                Ok(AudioControlMessage::Resume) => {
+                    let _ = stream.play();
+                }
+                Ok(AudioControlMessage::SwitchDevice { device_name }) => {
+                    switch_to = Some(device_name);
+                    break;
+                }
+                Err(_) => {}
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+
+        let Some(device_name) = switch_to else {
+            return Ok(());
+        };
+
+        let Some(new_device) = find_input_device(device_name.as_deref()) else {
+            continue;
+        };
+        let Ok(new_config) = new_device.default_input_config() else {
+            continue;
+        };
+        drop(stream);
+        device = new_device;
+        config = new_config;
+        if let Ok(name) = device.name() {
+            *CURRENT_MIC_DEVICE_ID.lock().unwrap() = enumerate_input_devices()
+                .into_iter()
+                .find(|d| d.name == name)
+                .map(|d| d.id);
+        }
+        reset_source("user");
+    }
+}
+
+/// Convert one cpal sample to i16 for the shared buffer/frame pipeline,
+/// regardless of the device's native sample type.
+trait ToI16: Copy {
+    fn to_i16(self) -> i16;
+}
+
+impl ToI16 for f32 {
+    fn to_i16(self) -> i16 {
+        (self * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16
+    }
+}
+
+impl ToI16 for f64 {
+    fn to_i16(self) -> i16 {
+        (self * i16::MAX as f64).clamp(i16::MIN as f64, i16::MAX as f64) as i16
+    }
+}
+
+impl ToI16 for i16 {
+    fn to_i16(self) -> i16 {
+        self
+    }
+}
+
+impl ToI16 for i32 {
+    fn to_i16(self) -> i16 {
+        (self >> 16) as i16
+    }
+}
+
+impl ToI16 for i8 {
+    fn to_i16(self) -> i16 {
+        (self as i16) << 8
+    }
+}
+
+impl ToI16 for u16 {
+    fn to_i16(self) -> i16 {
+        (self as i32 - 32768) as i16
+    }
+}
+
+impl ToI16 for u8 {
+    fn to_i16(self) -> i16 {
+        ((self as i16) - 128) << 8
+    }
+}
+
+impl ToI16 for i64 {
+    fn to_i16(self) -> i16 {
+        (self >> 48) as i16
+    }
+}
+
+impl ToI16 for u32 {
+    fn to_i16(self) -> i16 {
+        ((self as i64 - (u32::MAX as i64 / 2 + 1)) >> 16) as i16
+    }
+}
+
+impl ToI16 for u64 {
+    fn to_i16(self) -> i16 {
+        ((self as i128 - (u64::MAX as i128 / 2 + 1)) >> 48) as i16
+    }
+}
+
+/// Build an input stream for any cpal sample type, converting each sample to
+/// i16 via [`ToI16`] and pushing ~100ms frames onto the [`frame_bus`].
+fn build_capture_stream<T>(
+    device: &cpal::Device,
+    stream_config: &cpal::StreamConfig,
+    sample_rate: u32,
+    channels: u16,
+    chunk_size: usize,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: cpal::SizedSample + ToI16 + Send + 'static,
+{
+    use cpal::traits::DeviceTrait;
+
+    let mut buffer: Vec<i16> = Vec::with_capacity(chunk_size);
+    device.build_input_stream(
+        stream_config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            if !CAPTURING_MIC.load(Ordering::SeqCst) {
+                return;
+            }
+
+            buffer.extend(data.iter().map(|&sample| sample.to_i16()));
+
+            if buffer.len() >= chunk_size {
+                let _ = frame_bus().send(FrameBusEvent::Frame(AudioFrame {
+                    source: "user".to_string(),
+                    sample_rate,
+                    channels,
+                    samples: buffer.clone(),
+                    produced_at_ms: now_ms(),
+                }));
+                buffer.clear();
+            }
+        },
+        |err| {
+            eprintln!("Audio stream error: {}", err);
+        },
+        None,
+    )
+}
+
+/// Build a capture stream for whichever sample type `config` reports as the
+/// device's native format, dispatching to [`build_capture_stream`].
+fn build_stream_for_device(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+) -> Result<cpal::Stream, Box<dyn std::error::Error + Send + Sync>> {
+    use cpal::traits::DeviceTrait;
     use cpal::SampleFormat;
 
     println!("Using input device: {}", device.name()?);
@@ -204,181 +1351,257 @@ fn capture_audio_loop(
 
     let stream_config: cpal::StreamConfig = config.clone().into();
     let sample_rate = stream_config.sample_rate.0;
-    let channels = stream_config.channels as usize;
+    let channels = stream_config.channels as u16;
     let sample_format = config.sample_format();
 
-    // Buffer for collecting audio samples
-    let mut buffer: Vec<i16> = Vec::with_capacity(sample_rate as usize);
-
-    let window_clone = window.clone();
-
-    let chunk_size = (sample_rate as usize / 10) * channels;
+    let chunk_size = (sample_rate as usize / 10) * channels as usize;
 
     let stream = match sample_format {
-        SampleFormat::F32 => device.build_input_stream(
-            &stream_config,
-            move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                if !CAPTURING_MIC.load(Ordering::SeqCst) {
-                    return;
-                }
+        SampleFormat::F32 => {
+            build_capture_stream::<f32>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        SampleFormat::I16 => {
+            build_capture_stream::<i16>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        SampleFormat::U16 => {
+            build_capture_stream::<u16>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        SampleFormat::I32 => {
+            build_capture_stream::<i32>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        SampleFormat::I8 => {
+            build_capture_stream::<i8>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        SampleFormat::U8 => {
+            build_capture_stream::<u8>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        SampleFormat::F64 => {
+            build_capture_stream::<f64>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        SampleFormat::I64 => {
+            build_capture_stream::<i64>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        SampleFormat::U32 => {
+            build_capture_stream::<u32>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        SampleFormat::U64 => {
+            build_capture_stream::<u64>(device, &stream_config, sample_rate, channels, chunk_size)?
+        }
+        _ => {
+            return Err(format!("Unsupported sample format: {:?}", sample_format).into());
+        }
+    };
 
-                for &sample in data {
-                    let sample_i16 = (sample * 32767.0)
-                        .clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-                    buffer.push(sample_i16);
-                }
+    Ok(stream)
+}
 
-                if buffer.len() >= chunk_size {
-                    let bytes: Vec<u8> = buffer
-                        .iter()
-                        .flat_map(|&s| s.to_le_bytes())
-                        .collect();
-                    let payload = AudioChunk {
-                        speaker: "user".to_string(),
-                        data: bytes,
-                    };
-                    let _ = window_clone.emit("audio-chunk", payload);
-                    buffer.clear();
-                }
-            },
-            |err| {
-                eprintln!("Audio stream error: {}", err);
-            },
-            None,
-        )?,
-        SampleFormat::I16 => device.build_input_stream(
-            &stream_config,
-            move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                if !CAPTURING_MIC.load(Ordering::SeqCst) {
-                    return;
-                }
+fn capture_audio_loop(
+    device: cpal::Device,
+    config: cpal::SupportedStreamConfig,
+    mut control_rx: mpsc::Receiver<AudioControlMessage>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use cpal::traits::{DeviceTrait, StreamTrait};
 
-                buffer.extend_from_slice(data);
+    let mut device = device;
+    let mut config = config;
 
-                if buffer.len() >= chunk_size {
-                    let bytes: Vec<u8> = buffer
-                        .iter()
-                        .flat_map(|&s| s.to_le_bytes())
-                        .collect();
-                    let payload = AudioChunk {
-                        speaker: "user".to_string(),
-                        data: bytes,
-                    };
-                    let _ = window_clone.emit("audio-chunk", payload);
-                    buffer.clear();
-                }
-            },
-            |err| {
-                eprintln!("Audio stream error: {}", err);
-            },
-            None,
-        )?,
-        SampleFormat::U16 => device.build_input_stream(
-            &stream_config,
-            move |data: &[u16], _: &cpal::InputCallbackInfo| {
-                if !CAPTURING_MIC.load(Ordering::SeqCst) {
-                    return;
-                }
+    // Outer loop rebuilds the stream in place when `SwitchDevice` asks for a
+    // different device, without tearing down this thread or control
+    // channel. Inner loop pauses/resumes the current stream in place.
+    loop {
+        let stream = build_stream_for_device(&device, &config)?;
+        stream.play()?;
 
-                for &sample in data {
-                    let sample_i16 = (sample as i32 - 32768) as i16;
-                    buffer.push(sample_i16);
+        let mut switch_to = None;
+        while CAPTURING_MIC.load(Ordering::SeqCst) {
+            match control_rx.try_recv() {
+                Ok(AudioControlMessage::Stop) => return Ok(()),
+                Ok(AudioControlMessage::Pause) => {
+                    let _ = stream.pause();
                 }
-
-                if buffer.len() >= chunk_size {
-                    let bytes: Vec<u8> = buffer
-                        .iter()
-                        .flat_map(|&s| s.to_le_bytes())
-                        .collect();
-                    let payload = AudioChunk {
-                        speaker: "user".to_string(),
-                        data: bytes,
-                    };
-                    let _ = window_clone.emit("audio-chunk", payload);
-                    buffer.clear();
+                Ok(AudioControlMessage::Resume) => {
+                    let _ = stream.play();
                 }
-            },
-            |err| {
-                eprintln!("Audio stream error: {}", err);
-            },
-            None,
-        )?,
-        _ => {
-            return Err(format!("Unsupported sample format: {:?}", sample_format).into());
+                Ok(AudioControlMessage::SwitchDevice { device_name }) => {
+                    switch_to = Some(device_name);
+                    break;
+                }
+                Err(_) => {}
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
         }
-    };
 
-    stream.play()?;
+        let Some(device_name) = switch_to else {
+            return Ok(());
+        };
 
-    // Keep stream alive while capturing
-    while CAPTURING_MIC.load(Ordering::SeqCst) {
-        std::thread::sleep(std::time::Duration::from_millis(100));
+        let Some(new_device) = find_input_device(device_name.as_deref()) else {
+            continue;
+        };
+        let Ok(new_config) = new_device.default_input_config() else {
+            continue;
+        };
+        drop(stream);
+        device = new_device;
+        config = new_config;
+        reset_source("user");
     }
+}
 
-    Ok(())
+/// Find a render device by its WASAPI friendly name, falling back to the
+/// system default when `device_name` is `None` or doesn't match.
+fn find_render_device(
+    device_name: Option<&str>,
+) -> Result<wasapi::Device, Box<dyn std::error::Error + Send + Sync>> {
+    use wasapi::{DeviceCollection, DeviceEnumerator, Direction};
+
+    if let Some(name) = device_name {
+        let collection = DeviceCollection::new(&Direction::Render)?;
+        for idx in 0..collection.get_nbr_devices()? {
+            let device = collection.get_device_at_index(idx)?;
+            if device.get_friendlyname().ok().as_deref() == Some(name) {
+                return Ok(device);
+            }
+        }
+    }
+
+    let enumerator = DeviceEnumerator::new()?;
+    Ok(enumerator.get_default_device(&Direction::Render)?)
 }
 
 /// Capture system audio via WASAPI loopback and emit chunks.
 fn capture_loopback_audio(
-    window: tauri::Window,
+    app: tauri::AppHandle,
+    device_name: Option<String>,
+    output_format: Option<OutputFormat>,
+    mut control_rx: mpsc::Receiver<AudioControlMessage>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    use wasapi::{initialize_mta, DeviceEnumerator, Direction, StreamMode};
+    use wasapi::{initialize_mta, Direction, StreamMode};
 
     if let Err(err) = initialize_mta().ok() {
         return Err(format!("COM init failed: {err:?}").into());
     }
 
-    let enumerator = DeviceEnumerator::new()?;
-    let device = enumerator.get_default_device(&Direction::Render)?;
-    let mut audio_client = device.get_iaudioclient()?;
-
-    let wave_format = audio_client.get_mixformat()?;
-    let sample_rate = wave_format.wave_fmt.Format.nSamplesPerSec as u32;
-    let channels = wave_format.wave_fmt.Format.nChannels as u16;
-
-    // Notify frontend about the actual stream config
-    let _ = window.emit(
-        "audio-config",
-        AudioStreamConfig {
-            sample_rate,
-            channels,
-            speaker: "other".to_string(),
-        },
-    );
+    let mut device_name = device_name;
 
-    let mode = StreamMode::PollingShared {
-        autoconvert: true,
-        buffer_duration_hns: 200_000, // 20ms
-    };
-    audio_client.initialize_client(&wave_format, &Direction::Capture, &mode)?;
+    // Outer loop re-opens the audio client against a different render
+    // device when `SwitchDevice` asks for one, without tearing down this
+    // thread or control channel.
+    loop {
+        let device = find_render_device(device_name.as_deref())?;
 
-    let capture_client = audio_client.get_audiocaptureclient()?;
-    let bytes_per_frame = wave_format.get_blockalign() as usize;
+        if let Ok(name) = device.get_friendlyname() {
+            let current_id = enumerate_output_devices()
+                .into_iter()
+                .find(|d| d.name == name)
+                .map(|d| d.id);
+            *CURRENT_LOOPBACK_DEVICE_ID.lock().unwrap() = current_id;
+        }
 
-    audio_client.start_stream()?;
+        let mut audio_client = device.get_iaudioclient()?;
 
-    while CAPTURING_LOOPBACK.load(Ordering::SeqCst) {
-        let frames = capture_client
-            .get_next_packet_size()?
-            .unwrap_or(0);
-        if frames == 0 {
-            std::thread::sleep(std::time::Duration::from_millis(10));
-            continue;
+        let wave_format = audio_client.get_mixformat()?;
+        let sample_rate = wave_format.wave_fmt.Format.nSamplesPerSec as u32;
+        let channels = wave_format.wave_fmt.Format.nChannels as u16;
+
+        // Notify frontend about the actual stream config
+        let _ = app.emit(
+            "audio-config",
+            match output_format {
+                Some(format) => AudioStreamConfig {
+                    sample_rate: format.sample_rate,
+                    channels: 1,
+                    speaker: "other".to_string(),
+                    device_name: device.get_friendlyname().ok(),
+                    output_format,
+                },
+                None => AudioStreamConfig {
+                    sample_rate,
+                    channels,
+                    speaker: "other".to_string(),
+                    device_name: device.get_friendlyname().ok(),
+                    output_format: None,
+                },
+            },
+        );
+
+        let mode = StreamMode::PollingShared {
+            autoconvert: true,
+            buffer_duration_hns: 200_000, // 20ms
+        };
+        audio_client.initialize_client(&wave_format, &Direction::Capture, &mode)?;
+
+        let capture_client = audio_client.get_audiocaptureclient()?;
+        let bytes_per_frame = wave_format.get_blockalign() as usize;
+        let bytes_per_sample = bytes_per_frame / channels.max(1) as usize;
+
+        audio_client.start_stream()?;
+
+        // Paused in place via stop_stream/start_stream rather than dropping
+        // the audio client, so resuming doesn't re-initialize the device.
+        let mut paused = false;
+        let mut switch_to = None;
+
+        while CAPTURING_LOOPBACK.load(Ordering::SeqCst) {
+            match control_rx.try_recv() {
+                Ok(AudioControlMessage::Stop) => break,
+                Ok(AudioControlMessage::Pause) => {
+                    if !paused {
+                        audio_client.stop_stream()?;
+                        paused = true;
+                    }
+                }
+                Ok(AudioControlMessage::Resume) => {
+                    if paused {
+                        audio_client.start_stream()?;
+                        paused = false;
+                    }
+                }
+                Ok(AudioControlMessage::SwitchDevice { device_name }) => {
+                    switch_to = Some(device_name);
+                    break;
+                }
+                Err(_) => {}
+            }
+
+            if paused {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                continue;
+            }
+
+            let frames = capture_client
+                .get_next_packet_size()?
+                .unwrap_or(0);
+            if frames == 0 {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                continue;
+            }
+
+            let mut buffer = vec![0u8; frames as usize * bytes_per_frame];
+            let (frames_read, _) = capture_client.read_from_device(&mut buffer)?;
+            if frames_read > 0 {
+                let byte_len = frames_read as usize * bytes_per_frame;
+                buffer.truncate(byte_len);
+                let _ = frame_bus().send(FrameBusEvent::Frame(AudioFrame {
+                    source: "other".to_string(),
+                    sample_rate,
+                    channels,
+                    samples: decode_pcm_to_i16(&buffer, bytes_per_sample),
+                    produced_at_ms: now_ms(),
+                }));
+            }
         }
 
-        let mut buffer = vec![0u8; frames as usize * bytes_per_frame];
-        let (frames_read, _) = capture_client.read_from_device(&mut buffer)?;
-        if frames_read > 0 {
-            let byte_len = frames_read as usize * bytes_per_frame;
-            buffer.truncate(byte_len);
-            let payload = AudioChunk {
-                speaker: "other".to_string(),
-                data: buffer,
-            };
-            let _ = window.emit("audio-chunk", payload);
+        if !paused {
+            audio_client.stop_stream()?;
         }
-    }
 
-    audio_client.stop_stream()?;
-    Ok(())
+        match switch_to {
+            Some(new_name) => {
+                device_name = new_name;
+                reset_source("other");
+            }
+            None => return Ok(()),
+        }
+    }
 }
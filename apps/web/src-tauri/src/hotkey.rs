@@ -0,0 +1,198 @@
+//! Global push-to-talk hotkey bound to microphone capture.
+//!
+//! Supports both toggle mode (press once to start, press again to stop)
+//! and press-and-hold push-to-talk (capture while held, stop on release),
+//! so the tray-resident app can be used for dictation without focusing it.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::audio;
+use crate::logging;
+
+/// Default key combination registered on first launch.
+const DEFAULT_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+
+/// How the hotkey drives capture.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyMode {
+    /// Press once to start capture, press again to stop it.
+    Toggle,
+    /// Capture only while the key combination is held down.
+    PushToTalk,
+}
+
+/// The persisted hotkey binding.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HotkeyBinding {
+    /// Key combination in `tauri_plugin_global_shortcut` accelerator syntax.
+    pub shortcut: String,
+    /// Whether the hotkey toggles capture or behaves as push-to-talk.
+    pub mode: HotkeyMode,
+}
+
+impl Default for HotkeyBinding {
+    fn default() -> Self {
+        Self {
+            shortcut: DEFAULT_SHORTCUT.to_string(),
+            mode: HotkeyMode::Toggle,
+        }
+    }
+}
+
+static BINDING: Mutex<Option<HotkeyBinding>> = Mutex::new(None);
+
+/// `hotkey-triggered` event payload.
+#[derive(Debug, Serialize, Clone)]
+struct HotkeyTriggeredEvent {
+    mode: HotkeyMode,
+    recording: bool,
+}
+
+fn config_path() -> PathBuf {
+    logging::base_dir().join("projects").join("hotkey.json")
+}
+
+fn load_binding() -> HotkeyBinding {
+    fs::read_to_string(config_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_binding(binding: &HotkeyBinding) {
+    let path = config_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(text) = serde_json::to_string_pretty(binding) {
+        let _ = fs::write(path, text);
+    }
+}
+
+/// Register the persisted (or default) global shortcut. Called once from
+/// the `setup` closure.
+pub fn init(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let binding = load_binding();
+    let shortcut = parse_shortcut(&binding.shortcut)?;
+    app.global_shortcut().register(shortcut)?;
+    *BINDING.lock().unwrap() = Some(binding);
+    Ok(())
+}
+
+fn parse_shortcut(accelerator: &str) -> Result<Shortcut, Box<dyn std::error::Error>> {
+    accelerator.parse::<Shortcut>().map_err(Into::into)
+}
+
+/// Handle a global shortcut event, toggling or gating microphone capture
+/// according to the current binding's mode.
+pub fn on_shortcut(app: &AppHandle, _shortcut: &Shortcut, event_state: ShortcutState) {
+    let mode = BINDING
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|b| b.mode)
+        .unwrap_or(HotkeyMode::Toggle);
+
+    match mode {
+        HotkeyMode::Toggle => {
+            if event_state == ShortcutState::Pressed {
+                if audio::is_mic_capturing() {
+                    stop(app);
+                } else {
+                    start(app);
+                }
+            }
+        }
+        HotkeyMode::PushToTalk => match event_state {
+            ShortcutState::Pressed => start(app),
+            ShortcutState::Released => stop(app),
+        },
+    }
+}
+
+fn start(app: &AppHandle) {
+    let app_clone = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if audio::start_microphone_capture(app_clone.clone(), None, None)
+            .await
+            .is_ok()
+        {
+            let _ = app_clone.emit(
+                "hotkey-triggered",
+                HotkeyTriggeredEvent {
+                    mode: current_mode(),
+                    recording: true,
+                },
+            );
+        }
+    });
+}
+
+fn stop(app: &AppHandle) {
+    let app_clone = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = audio::stop_capture().await;
+        let _ = app_clone.emit(
+            "hotkey-triggered",
+            HotkeyTriggeredEvent {
+                mode: current_mode(),
+                recording: false,
+            },
+        );
+    });
+}
+
+fn current_mode() -> HotkeyMode {
+    BINDING
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|b| b.mode)
+        .unwrap_or(HotkeyMode::Toggle)
+}
+
+/// Read the current hotkey binding.
+#[tauri::command]
+pub fn get_hotkey_binding() -> HotkeyBinding {
+    BINDING.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// Rebind the global shortcut and/or its mode at runtime, persisting the
+/// new binding across restarts.
+///
+/// Registers the new shortcut before touching the old one, so a failed
+/// registration (an invalid accelerator, or one the OS already owns) leaves
+/// the previous binding active and registered instead of dropping the user
+/// to no hotkey at all.
+#[tauri::command]
+pub fn set_hotkey_binding(app: AppHandle, binding: HotkeyBinding) -> Result<(), String> {
+    let new_shortcut = parse_shortcut(&binding.shortcut).map_err(|e| e.to_string())?;
+
+    let previous = BINDING.lock().unwrap().clone();
+    let shortcut_unchanged = previous
+        .as_ref()
+        .is_some_and(|p| p.shortcut == binding.shortcut);
+
+    if !shortcut_unchanged {
+        app.global_shortcut()
+            .register(new_shortcut)
+            .map_err(|e| e.to_string())?;
+
+        if let Some(previous) = &previous {
+            if let Ok(old_shortcut) = parse_shortcut(&previous.shortcut) {
+                let _ = app.global_shortcut().unregister(old_shortcut);
+            }
+        }
+    }
+
+    save_binding(&binding);
+    *BINDING.lock().unwrap() = Some(binding);
+    Ok(())
+}
@@ -2,14 +2,13 @@
 //!
 //! Provides audio capture functionality and IPC commands for the desktop app.
 
-use std::net::TcpListener;
-use tauri::Emitter;
 use tauri::Manager;
-use tauri_plugin_shell::ShellExt;
-use tauri_plugin_shell::process::CommandEvent;
 
 mod audio;
+mod hotkey;
 mod logging;
+mod sidecar;
+mod updater;
 
 /// Initialize and run the Tauri application.
 ///
@@ -27,6 +26,15 @@ pub fn run() {
     // endregion
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    hotkey::on_shortcut(app, shortcut, event.state());
+                })
+                .build(),
+        )
         .setup(|_app| {
             let app = _app.app_handle();
             // region agent log
@@ -75,146 +83,16 @@ pub fn run() {
                 })
                 .build(app)?;
 
+            hotkey::init(app)?;
+            audio::start_device_watcher(app.clone());
+            audio::start_sidecar_forwarder(app.clone());
+            audio::start_level_meter_consumer(app.clone());
+            audio::start_mixer_consumer(app.clone());
+            updater::start(app.clone());
+
             #[cfg(not(debug_assertions))]
             {
-                let log_path = logging::sidecar_log_path();
-                let ready_listener = TcpListener::bind("127.0.0.1:0").ok();
-                let ready_port = ready_listener
-                    .as_ref()
-                    .and_then(|listener| listener.local_addr().ok())
-                    .map(|addr| addr.port());
-                // region agent log
-                logging::append_debug_log(
-                    "H5",
-                    "lib.rs:setup",
-                    "sidecar_ready_listener",
-                    serde_json::json!({ "port": ready_port }),
-                );
-                // endregion
-                // region agent log
-                logging::append_debug_log(
-                    "H1",
-                    "lib.rs:setup",
-                    "sidecar_spawn_attempt",
-                    serde_json::json!({ "log_path": log_path }),
-                );
-                // endregion
-                if let Some(parent) = log_path.parent() {
-                    let _ = std::fs::create_dir_all(parent);
-                }
-
-                if let Ok(command) = app.shell().sidecar("voicecopilot-api") {
-                    let command = command
-                        .current_dir(logging::base_dir())
-                        .env("VOICECOPILOT_LOG_PATH", log_path.to_string_lossy().to_string());
-                    let command = if let Some(port) = ready_port {
-                        command.env("VOICECOPILOT_READY_PORT", port.to_string())
-                    } else {
-                        command
-                    };
-                    match command.spawn() {
-                        Ok((mut rx, _child)) => {
-                            logging::append_log("Sidecar started");
-                            // region agent log
-                            logging::append_debug_log(
-                                "H1",
-                                "lib.rs:setup",
-                                "sidecar_spawn_ok",
-                                serde_json::json!({}),
-                            );
-                            // endregion
-                            if let Some(listener) = ready_listener {
-                                let app_handle = app.clone();
-                                std::thread::spawn(move || {
-                                    if let Ok((_stream, _addr)) = listener.accept() {
-                                        let _ = app_handle.emit("sidecar-ready", ());
-                                        // region agent log
-                                        logging::append_debug_log(
-                                            "H5",
-                                            "lib.rs:setup",
-                                            "sidecar_ready_tcp",
-                                            serde_json::json!({}),
-                                        );
-                                        // endregion
-                                    }
-                                });
-                            }
-                            let app_handle = app.clone();
-                            tauri::async_runtime::spawn(async move {
-                                let mut stdout_logged = false;
-                                let mut stderr_logged = false;
-                                while let Some(event) = rx.recv().await {
-                                    match &event {
-                                        CommandEvent::Stdout(line) | CommandEvent::Stderr(line) => {
-                                            let text = String::from_utf8_lossy(line);
-                                            if !stdout_logged && matches!(event, CommandEvent::Stdout(_)) {
-                                                stdout_logged = true;
-                                                // region agent log
-                                                logging::append_debug_log(
-                                                    "H5",
-                                                    "lib.rs:setup",
-                                                    "sidecar_stdout_line",
-                                                    serde_json::json!({ "line": text.chars().take(120).collect::<String>() }),
-                                                );
-                                                // endregion
-                                            }
-                                            if !stderr_logged && matches!(event, CommandEvent::Stderr(_)) {
-                                                stderr_logged = true;
-                                                // region agent log
-                                                logging::append_debug_log(
-                                                    "H5",
-                                                    "lib.rs:setup",
-                                                    "sidecar_stderr_line",
-                                                    serde_json::json!({ "line": text.chars().take(120).collect::<String>() }),
-                                                );
-                                                // endregion
-                                            }
-                                            if text.contains("Uvicorn running on")
-                                                || text.contains("Application startup complete")
-                                            {
-                                                let _ = app_handle.emit("sidecar-ready", ());
-                                                // region agent log
-                                                logging::append_debug_log(
-                                                    "H5",
-                                                    "lib.rs:setup",
-                                                    "sidecar_ready_emitted",
-                                                    serde_json::json!({ "message": text.to_string() }),
-                                                );
-                                                // endregion
-                                                break;
-                                            }
-                                        }
-                                        CommandEvent::Error(error) => {
-                                            // region agent log
-                                            logging::append_debug_log(
-                                                "H5",
-                                                "lib.rs:setup",
-                                                "sidecar_command_error",
-                                                serde_json::json!({ "error": error }),
-                                            );
-                                            // endregion
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            });
-                        }
-                        Err(error) => logging::append_log(&format!(
-                            "Sidecar failed to start: {}",
-                            error
-                        )),
-                    }
-                } else {
-                    logging::append_log("Sidecar is not configured");
-                    // region agent log
-                    logging::append_debug_log(
-                        "H1",
-                        "lib.rs:setup",
-                        "sidecar_not_configured",
-                        serde_json::json!({}),
-                    );
-                    // endregion
-                }
+                sidecar::start(app.clone());
             }
             Ok(())
         })
@@ -223,7 +101,22 @@ pub fn run() {
             audio::start_microphone_capture,
             audio::start_loopback_capture,
             audio::stop_capture,
+            audio::pause_capture,
+            audio::resume_capture,
+            audio::switch_microphone_device,
+            audio::switch_loopback_device,
             audio::get_audio_devices,
+            audio::set_audio_level_config,
+            audio::get_audio_level_config,
+            sidecar::get_sidecar_state,
+            sidecar::restart_sidecar,
+            hotkey::get_hotkey_binding,
+            hotkey::set_hotkey_binding,
+            logging::get_recent_logs,
+            logging::open_log_directory,
+            logging::get_log_level,
+            logging::set_log_level,
+            updater::check_for_update,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,13 +1,79 @@
-//! Simple file logging for the desktop app.
+//! Structured, rotating file logging for the desktop app.
+//!
+//! Log locations are resolved from the `VOICECOPILOT_LOG_DIR` environment
+//! variable (falling back to a directory next to the executable) rather
+//! than hardcoded, both
+//! the desktop and debug logs are newline-delimited JSON, and writes are
+//! rotated by size with a retention cap so logs don't grow unbounded.
 
-use std::fs::{create_dir_all, OpenOptions};
+use std::fs::{self, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-const DEBUG_LOG_PATH: &str = r"d:\vladexecute\proj\VoiceCoPilot\.cursor\debug.log";
+/// Log file size cap before it's rotated.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// How many rotated files are kept per log, beyond the active one.
+const MAX_ROTATED_FILES: usize = 5;
+
+/// Log severity. `Debug` carries the noisy `append_debug_log` hypothesis
+/// traces and is disabled by default in release builds.
+#[repr(u8)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Debug = 0,
+    Info = 1,
+    Warn = 2,
+    Error = 3,
+}
+
+impl LogLevel {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LogLevel::Debug,
+            1 => LogLevel::Info,
+            2 => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+static CURRENT_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Debug as u8);
+#[cfg(not(debug_assertions))]
+static CURRENT_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+fn level_enabled(level: LogLevel) -> bool {
+    level >= LogLevel::from_u8(CURRENT_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Read the current minimum log level.
+#[tauri::command]
+pub fn get_log_level() -> LogLevel {
+    LogLevel::from_u8(CURRENT_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Set the minimum log level; records below it are dropped without being
+/// written.
+#[tauri::command]
+pub fn set_log_level(level: LogLevel) {
+    CURRENT_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// One newline-delimited JSON log record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogRecord {
+    pub timestamp_ms: u128,
+    pub level: LogLevel,
+    pub message: String,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
 
 /// Resolve the base directory (next to the executable).
 pub fn base_dir() -> PathBuf {
@@ -18,9 +84,12 @@ pub fn base_dir() -> PathBuf {
         .unwrap_or_else(|| PathBuf::from("."))
 }
 
-/// Resolve the log directory under the projects folder.
+/// Resolve the log directory, honoring `VOICECOPILOT_LOG_DIR` so deployments
+/// can redirect logs without a rebuild; never a hardcoded dev path.
 pub fn log_dir() -> PathBuf {
-    base_dir().join("projects").join("logs")
+    std::env::var("VOICECOPILOT_LOG_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| base_dir().join("projects").join("logs"))
 }
 
 /// Resolve the desktop log file path.
@@ -33,43 +102,108 @@ pub fn sidecar_log_path() -> PathBuf {
     log_dir().join("api.log")
 }
 
-/// Append a line to the desktop log file.
-pub fn append_log(message: &str) {
-    let log_path = desktop_log_path();
-    if let Some(parent) = log_path.parent() {
-        let _ = create_dir_all(parent);
-    }
+/// Resolve the debug/hypothesis log file path.
+pub fn debug_log_path() -> PathBuf {
+    log_dir().join("debug.log")
+}
 
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|value| value.as_secs())
-        .unwrap_or(0);
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut os_str = path.as_os_str().to_owned();
+    os_str.push(format!(".{n}"));
+    PathBuf::from(os_str)
+}
+
+/// Rotate `path` to `path.1` (shifting older rotations up, dropping the
+/// oldest beyond [`MAX_ROTATED_FILES`]) once it exceeds [`MAX_LOG_BYTES`].
+fn rotate_if_needed(path: &Path) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    if metadata.len() < MAX_LOG_BYTES {
+        return;
+    }
 
-    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&log_path) {
-        let _ = writeln!(file, "[{}] {}", timestamp, message);
+    for i in (1..MAX_ROTATED_FILES).rev() {
+        let from = rotated_path(path, i);
+        if from.exists() {
+            let _ = fs::rename(&from, rotated_path(path, i + 1));
+        }
     }
+    let _ = fs::rename(path, rotated_path(path, 1));
 }
 
-/// Append a debug log entry to the debug log file.
-pub fn append_debug_log(hypothesis_id: &str, location: &str, message: &str, data: serde_json::Value) {
-    let payload = json!({
-        "sessionId": "debug-session",
-        "runId": "run1",
-        "hypothesisId": hypothesis_id,
-        "location": location,
-        "message": message,
-        "data": data,
-        "timestamp": SystemTime::now()
+fn write_record(path: PathBuf, level: LogLevel, message: &str, data: serde_json::Value) {
+    if !level_enabled(level) {
+        return;
+    }
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    rotate_if_needed(&path);
+
+    let record = LogRecord {
+        timestamp_ms: SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|value| value.as_millis())
             .unwrap_or(0),
-    });
+        level,
+        message: message.to_string(),
+        data,
+    };
 
-    let debug_path = PathBuf::from(DEBUG_LOG_PATH);
-    if let Some(parent) = debug_path.parent() {
-        let _ = create_dir_all(parent);
-    }
-    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(debug_path) {
-        let _ = writeln!(file, "{}", payload.to_string());
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        if let Ok(line) = serde_json::to_string(&record) {
+            let _ = writeln!(file, "{}", line);
+        }
     }
 }
+
+/// Append an info-level line to the desktop log.
+pub fn append_log(message: &str) {
+    write_record(desktop_log_path(), LogLevel::Info, message, json!(null));
+}
+
+/// Append a debug-level hypothesis trace to the debug log. Disabled by
+/// default outside debug builds; re-enable via [`set_log_level`].
+pub fn append_debug_log(hypothesis_id: &str, location: &str, message: &str, data: serde_json::Value) {
+    write_record(
+        debug_log_path(),
+        LogLevel::Debug,
+        message,
+        json!({ "hypothesisId": hypothesis_id, "location": location, "data": data }),
+    );
+}
+
+/// Fetch the last `limit` records from the desktop, debug, or sidecar log.
+#[tauri::command]
+pub fn get_recent_logs(kind: String, limit: usize) -> Result<Vec<LogRecord>, String> {
+    let path = match kind.as_str() {
+        "desktop" => desktop_log_path(),
+        "debug" => debug_log_path(),
+        "sidecar" => sidecar_log_path(),
+        other => return Err(format!("Unknown log kind: {other}")),
+    };
+
+    let text = fs::read_to_string(&path).unwrap_or_default();
+    let records: Vec<LogRecord> = text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    let start = records.len().saturating_sub(limit);
+    Ok(records[start..].to_vec())
+}
+
+/// Reveal the log directory in the system file manager, creating it first
+/// if it doesn't exist yet.
+#[tauri::command]
+pub fn open_log_directory(app: tauri::AppHandle) -> Result<(), String> {
+    use tauri_plugin_shell::ShellExt;
+
+    let dir = log_dir();
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    app.shell()
+        .open(dir.to_string_lossy().to_string(), None)
+        .map_err(|e| e.to_string())
+}
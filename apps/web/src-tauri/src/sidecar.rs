@@ -0,0 +1,256 @@
+//! Supervises the bundled `voicecopilot-api` sidecar process.
+//!
+//! Unlike a one-shot spawn, this keeps reading the `CommandEvent` stream for
+//! the whole process lifetime, restarts the sidecar with exponential backoff
+//! on crash/exit, and tracks readiness via the `VOICECOPILOT_READY_PORT` TCP
+//! handshake with a timeout.
+
+use std::net::TcpListener as StdTcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::logging;
+
+/// Backoff before the first restart attempt after a crash.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Ceiling for the exponential backoff between restart attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// A run lasting at least this long is considered stable and resets backoff.
+const STABLE_RUN: Duration = Duration::from_secs(60);
+/// How long to wait for the ready TCP handshake before giving up on it.
+const READY_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Lifecycle state of the supervised sidecar, mirrored to the frontend.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SidecarState {
+    /// Process has been spawned, readiness handshake not yet observed.
+    Starting,
+    /// Readiness handshake succeeded; the sidecar is serving requests.
+    Ready,
+    /// The previous run ended and a restart is pending (backoff or forced).
+    Restarting,
+    /// The process exited or errored out.
+    Crashed,
+}
+
+static CURRENT_STATE: Mutex<SidecarState> = Mutex::new(SidecarState::Starting);
+static CHILD: Mutex<Option<CommandChild>> = Mutex::new(None);
+static FORCE_RESTART: AtomicBool = AtomicBool::new(false);
+/// Set while the updater is replacing the sidecar binary, so the supervisor
+/// parks instead of respawning the process it was just told to kill.
+static UPDATE_PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// How long the supervisor sleeps between checks while paused for an update.
+const UPDATE_PAUSE_POLL: Duration = Duration::from_millis(200);
+
+fn set_state(app: &AppHandle, state: SidecarState) {
+    if let Ok(mut guard) = CURRENT_STATE.lock() {
+        *guard = state;
+    }
+    let event = match state {
+        SidecarState::Starting => return,
+        SidecarState::Ready => "sidecar-ready",
+        SidecarState::Restarting => "sidecar-restarting",
+        SidecarState::Crashed => "sidecar-crashed",
+    };
+    let _ = app.emit(event, state);
+}
+
+/// Start the supervisor loop. Spawns the sidecar, restarts it on crash with
+/// exponential backoff, and keeps doing so for the lifetime of the app.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            if UPDATE_PAUSED.load(Ordering::SeqCst) {
+                tokio::time::sleep(UPDATE_PAUSE_POLL).await;
+                continue;
+            }
+
+            set_state(&app, SidecarState::Starting);
+            let launched_at = Instant::now();
+
+            if let Err(reason) = launch_once(&app).await {
+                logging::append_log(&format!("Sidecar stopped: {reason}"));
+            }
+
+            // `pause_for_update` kills the child to let the updater replace
+            // the binary; that exit surfaces through `launch_once` just like
+            // a crash would, but it's an intentional stop, not a failure.
+            // Park immediately instead of reporting a crash/restart the
+            // frontend never asked for.
+            if UPDATE_PAUSED.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            if launched_at.elapsed() >= STABLE_RUN {
+                backoff = INITIAL_BACKOFF;
+            }
+
+            set_state(&app, SidecarState::Crashed);
+
+            if FORCE_RESTART.swap(false, Ordering::SeqCst) {
+                logging::append_log("Restarting sidecar immediately (forced)");
+                set_state(&app, SidecarState::Restarting);
+            } else {
+                logging::append_log(&format!("Restarting sidecar in {:?}", backoff));
+                set_state(&app, SidecarState::Restarting);
+                // Wait out the backoff in short slices so `restart_sidecar`
+                // can cut it short.
+                let deadline = Instant::now() + backoff;
+                while Instant::now() < deadline && !FORCE_RESTART.swap(false, Ordering::SeqCst) {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    });
+}
+
+/// Spawn the sidecar once and drive its event stream to completion, returning
+/// once the process exits, errors, or its stream closes.
+async fn launch_once(app: &AppHandle) -> Result<(), String> {
+    let log_path = logging::sidecar_log_path();
+    if let Some(parent) = log_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    let std_listener = StdTcpListener::bind("127.0.0.1:0").map_err(|e| e.to_string())?;
+    std_listener
+        .set_nonblocking(true)
+        .map_err(|e| e.to_string())?;
+    let ready_port = std_listener
+        .local_addr()
+        .map_err(|e| e.to_string())?
+        .port();
+    let ready_listener =
+        tokio::net::TcpListener::from_std(std_listener).map_err(|e| e.to_string())?;
+
+    logging::append_debug_log(
+        "H1",
+        "sidecar.rs:launch_once",
+        "sidecar_spawn_attempt",
+        serde_json::json!({ "log_path": log_path, "ready_port": ready_port }),
+    );
+
+    let command = app
+        .shell()
+        .sidecar("voicecopilot-api")
+        .map_err(|e| e.to_string())?
+        .current_dir(logging::base_dir())
+        .env(
+            "VOICECOPILOT_LOG_PATH",
+            log_path.to_string_lossy().to_string(),
+        )
+        .env("VOICECOPILOT_READY_PORT", ready_port.to_string());
+
+    let (mut rx, child) = command.spawn().map_err(|e| e.to_string())?;
+    *CHILD.lock().unwrap() = Some(child);
+    logging::append_log("Sidecar started");
+    logging::append_debug_log(
+        "H1",
+        "sidecar.rs:launch_once",
+        "sidecar_spawn_ok",
+        serde_json::json!({}),
+    );
+
+    let app_for_ready = app.clone();
+    let ready_task = tauri::async_runtime::spawn(async move {
+        match tokio::time::timeout(READY_TIMEOUT, ready_listener.accept()).await {
+            Ok(Ok(_)) => {
+                set_state(&app_for_ready, SidecarState::Ready);
+                logging::append_debug_log(
+                    "H5",
+                    "sidecar.rs:launch_once",
+                    "sidecar_ready_tcp",
+                    serde_json::json!({}),
+                );
+            }
+            _ => {
+                logging::append_log("Sidecar readiness handshake timed out");
+            }
+        }
+    });
+
+    let exit_reason = loop {
+        match rx.recv().await {
+            Some(CommandEvent::Stdout(line)) => {
+                logging::append_debug_log(
+                    "H5",
+                    "sidecar.rs:launch_once",
+                    "sidecar_stdout_line",
+                    serde_json::json!({ "line": String::from_utf8_lossy(&line).chars().take(120).collect::<String>() }),
+                );
+            }
+            Some(CommandEvent::Stderr(line)) => {
+                logging::append_debug_log(
+                    "H5",
+                    "sidecar.rs:launch_once",
+                    "sidecar_stderr_line",
+                    serde_json::json!({ "line": String::from_utf8_lossy(&line).chars().take(120).collect::<String>() }),
+                );
+            }
+            Some(CommandEvent::Error(error)) => {
+                logging::append_debug_log(
+                    "H6",
+                    "sidecar.rs:launch_once",
+                    "sidecar_command_error",
+                    serde_json::json!({ "error": error }),
+                );
+                break format!("sidecar error: {error}");
+            }
+            Some(CommandEvent::Terminated(payload)) => {
+                break format!("sidecar exited with code {:?}", payload.code);
+            }
+            Some(_) => {}
+            None => break "sidecar event stream closed".to_string(),
+        }
+    };
+
+    ready_task.abort();
+    *CHILD.lock().unwrap() = None;
+    Err(exit_reason)
+}
+
+/// Query the supervisor's current view of the sidecar's lifecycle state.
+#[tauri::command]
+pub fn get_sidecar_state() -> SidecarState {
+    CURRENT_STATE
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or(SidecarState::Crashed)
+}
+
+/// Force an immediate sidecar restart, bypassing the current backoff wait.
+#[tauri::command]
+pub fn restart_sidecar() -> Result<(), String> {
+    FORCE_RESTART.store(true, Ordering::SeqCst);
+    if let Some(child) = CHILD.lock().unwrap().take() {
+        child.kill().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Stop the sidecar and park the supervisor so the updater can safely
+/// replace the bundled binary on disk.
+pub fn pause_for_update() {
+    UPDATE_PAUSED.store(true, Ordering::SeqCst);
+    if let Some(child) = CHILD.lock().unwrap().take() {
+        let _ = child.kill();
+    }
+    logging::append_log("Sidecar paused for update");
+}
+
+/// Let the supervisor resume spawning the sidecar after an update attempt,
+/// whether it succeeded or failed.
+pub fn resume_after_update() {
+    UPDATE_PAUSED.store(false, Ordering::SeqCst);
+    logging::append_log("Sidecar resumed after update");
+}
@@ -0,0 +1,95 @@
+//! Auto-update subsystem for the desktop app and its bundled sidecar.
+//!
+//! Checks the configured release endpoint for a newer build, verifies the
+//! download against the updater plugin's embedded public key, and
+//! coordinates with the sidecar supervisor so `voicecopilot-api` is stopped
+//! before its binary is replaced and only resumed once the attempt is over.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_process::ProcessExt;
+use tauri_plugin_updater::UpdaterExt;
+
+use crate::logging;
+use crate::sidecar;
+
+/// `update-available` event payload.
+#[derive(Debug, Serialize, Clone)]
+struct UpdateAvailableEvent {
+    version: String,
+    current_version: String,
+}
+
+/// `update-progress` event payload.
+#[derive(Debug, Serialize, Clone)]
+struct UpdateProgressEvent {
+    downloaded_bytes: usize,
+    total_bytes: Option<u64>,
+}
+
+/// Check the release endpoint once, and if a verified update is available,
+/// stop the sidecar, download and install it, then relaunch.
+async fn check(app: &AppHandle) -> Result<(), String> {
+    let updater = app.updater().map_err(|e| e.to_string())?;
+    let Some(update) = updater.check().await.map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+
+    logging::append_log(&format!("Update available: {}", update.version));
+    let _ = app.emit(
+        "update-available",
+        UpdateAvailableEvent {
+            version: update.version.clone(),
+            current_version: update.current_version.clone(),
+        },
+    );
+
+    sidecar::pause_for_update();
+
+    let app_for_progress = app.clone();
+    let mut downloaded_bytes = 0usize;
+    let install_result = update
+        .download_and_install(
+            move |chunk_length, total_bytes| {
+                downloaded_bytes += chunk_length;
+                let _ = app_for_progress.emit(
+                    "update-progress",
+                    UpdateProgressEvent {
+                        downloaded_bytes,
+                        total_bytes,
+                    },
+                );
+            },
+            || {},
+        )
+        .await;
+
+    match install_result {
+        Ok(()) => {
+            logging::append_log("Update downloaded and verified, relaunching");
+            let _ = app.emit("update-ready", ());
+            app.restart();
+        }
+        Err(e) => {
+            logging::append_log(&format!("Update failed: {e}"));
+            sidecar::resume_after_update();
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Kick off a startup update check as a background task. Called once from
+/// the `setup` closure.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = check(&app).await {
+            logging::append_log(&format!("Startup update check failed: {e}"));
+        }
+    });
+}
+
+/// Check for an update on demand, e.g. from a "Check for updates" menu item.
+#[tauri::command]
+pub async fn check_for_update(app: AppHandle) -> Result<(), String> {
+    check(&app).await
+}